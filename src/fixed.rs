@@ -0,0 +1,126 @@
+//! Fixed-capacity, allocation-free heap backed by a `[T; N]` array instead of a
+//! `Vec`, for callers that want a bounded upper size known at compile time. The
+//! crate as a whole still depends on `std`, so this type doesn't make the
+//! crate usable from a `#![no_std]` binary on its own.
+//! Shares its sift-up/down logic with [`crate::BinaryHeap`] via [`crate::sift`].
+
+use crate::sift;
+use crate::{Compare, MaxComparator, MinComparator};
+
+/// Array-backed heap with a compile-time capacity of `N`. Never allocates;
+/// [`push`](Self::push) fails (handing the value back) once full instead of growing.
+///
+/// Deliberately does *not* implement `bytemuck::Pod`, even with the
+/// `bytemuck` feature enabled: for an arbitrary `T`/`N`/`C` the compiler can
+/// insert uninitialized padding bytes between `len`, `data` and `cmp`, and
+/// reading those as initialized data would be unsound. `bytemuck`'s derive
+/// refuses to verify that generically, so there's no single impl that's
+/// correct for every instantiation of this type.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct FixedBinaryHeap<T, const N: usize, C = MinComparator> {
+    len: usize,
+    data: [T; N],
+    cmp: C,
+}
+
+impl<T: Copy + Default, const N: usize> FixedBinaryHeap<T, N, MinComparator> {
+    pub fn new() -> Self {
+        FixedBinaryHeap {
+            len: 0,
+            data: [T::default(); N],
+            cmp: MinComparator,
+        }
+    }
+}
+impl<T: Copy + Default, const N: usize> FixedBinaryHeap<T, N, MaxComparator> {
+    pub fn new_max() -> Self {
+        FixedBinaryHeap {
+            len: 0,
+            data: [T::default(); N],
+            cmp: MaxComparator,
+        }
+    }
+}
+
+impl<T: Copy + Default, const N: usize, C: Compare<T>> FixedBinaryHeap<T, N, C> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// total number of elements this heap can hold, i.e. `N`
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// pushes `val`, or hands it back in `Err` if the heap is already at capacity
+    pub fn push(&mut self, val: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(val);
+        }
+        let idx = self.len;
+        self.data[idx] = val;
+        self.len += 1;
+        sift::flow_up(&mut self.data[..self.len], &self.cmp, idx);
+        Ok(())
+    }
+
+    /// removes and returns the top element (the minimum for the default
+    /// comparator, the maximum for [`MaxComparator`]), `None` if empty
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let end = self.len - 1;
+        self.data.swap(0, end);
+        let val = self.data[end];
+        self.len -= 1;
+        sift::flow_down(&mut self.data[..self.len], &self.cmp, 0);
+        Some(val)
+    }
+
+    /// returns the top element (the one [`pop`](Self::pop) would remove), `None` if empty
+    pub fn peek(&self) -> Option<&T> {
+        if self.len == 0 {
+            None
+        } else {
+            Some(&self.data[0])
+        }
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Default for FixedBinaryHeap<T, N, MinComparator> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedBinaryHeap;
+
+    #[test]
+    fn push_pop_respects_capacity() {
+        let mut heap: FixedBinaryHeap<i32, 3> = FixedBinaryHeap::new();
+        assert_eq!(heap.push(5), Ok(()));
+        assert_eq!(heap.push(1), Ok(()));
+        assert_eq!(heap.push(8), Ok(()));
+        assert_eq!(heap.push(3), Err(3));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(5));
+        assert_eq!(heap.pop(), Some(8));
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn max_variant_pops_largest_first() {
+        let mut heap: FixedBinaryHeap<i32, 4, crate::MaxComparator> = FixedBinaryHeap::new_max();
+        for v in [5, 1, 8, 3] {
+            heap.push(v).unwrap();
+        }
+        assert_eq!(heap.pop(), Some(8));
+        assert_eq!(heap.pop(), Some(5));
+    }
+}
@@ -0,0 +1,250 @@
+//! Indexed priority queue: a [`crate::BinaryHeap`] variant that hands back a
+//! stable [`Handle`] on `push`, so an element already inside the heap can have
+//! its priority lowered or raised (e.g. Dijkstra's tentative-distance update)
+//! without a full rebuild.
+
+use crate::sift;
+use crate::{Compare, MaxComparator, MinComparator};
+use std::cmp::Ordering;
+
+/// Stable reference to an element stored in an [`IndexedBinaryHeap`]. Remains
+/// valid while that element is in the heap, even as its position inside the
+/// heap changes; once the element is removed (via `remove` or `pop`), using
+/// the handle panics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle(usize);
+
+struct Entry<T> {
+    handle: Handle,
+    val: T,
+}
+
+/// Priority queue that, unlike [`crate::BinaryHeap`], lets you update or
+/// remove an element already inside it in O(log n) via its [`Handle`].
+pub struct IndexedBinaryHeap<T, C = MinComparator> {
+    vec: Vec<Entry<T>>,
+    // position[handle.0] is the current index of that handle's element in `vec`,
+    // meaningful only while removed[handle.0] is false
+    position: Vec<usize>,
+    // removed[handle.0] is set once that handle's element leaves the heap, so a
+    // handle can never be silently reinterpreted as whatever element later ends
+    // up in its old slot
+    removed: Vec<bool>,
+    cmp: C,
+}
+
+impl<T: Ord> IndexedBinaryHeap<T, MinComparator> {
+    pub fn new() -> Self {
+        IndexedBinaryHeap {
+            vec: Vec::new(),
+            position: Vec::new(),
+            removed: Vec::new(),
+            cmp: MinComparator,
+        }
+    }
+}
+impl<T: Ord> IndexedBinaryHeap<T, MaxComparator> {
+    pub fn new_max() -> Self {
+        IndexedBinaryHeap {
+            vec: Vec::new(),
+            position: Vec::new(),
+            removed: Vec::new(),
+            cmp: MaxComparator,
+        }
+    }
+}
+
+impl<T, C: Compare<T>> IndexedBinaryHeap<T, C> {
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    fn higher_in_tree(&self, you: usize, they: usize) -> bool {
+        self.cmp.compares(&self.vec[you].val, &self.vec[they].val) == Ordering::Less
+    }
+    fn swap(&mut self, i: usize, j: usize) {
+        self.position[self.vec[i].handle.0] = j;
+        self.position[self.vec[j].handle.0] = i;
+        self.vec.swap(i, j);
+    }
+    /// panics if `handle` refers to an element that has already been removed
+    /// (via `remove` or `pop`) rather than one currently in the heap
+    fn assert_valid(&self, handle: Handle) {
+        assert!(
+            !self.removed[handle.0],
+            "Handle used after its element was removed from the IndexedBinaryHeap"
+        );
+    }
+    /// sifts the element at `idx` up towards the root, returns its final index
+    fn flow_up(&mut self, mut idx: usize) -> usize {
+        loop {
+            if idx == 0 {
+                return idx;
+            }
+            let parent_idx = sift::parent_idx(idx);
+            if self.higher_in_tree(idx, parent_idx) {
+                self.swap(parent_idx, idx);
+                idx = parent_idx;
+            } else {
+                return idx;
+            }
+        }
+    }
+    /// sifts the element at `idx` down towards the leaves, returns its final index
+    fn flow_down(&mut self, mut idx: usize) -> usize {
+        loop {
+            let (child1_idx, child2_idx) = sift::child_idxs(idx);
+            let highest_child_idx = if child1_idx >= self.vec.len() {
+                return idx; // don't have any children
+            } else if child2_idx >= self.vec.len() || self.higher_in_tree(child1_idx, child2_idx) {
+                child1_idx
+            } else {
+                child2_idx
+            };
+            if self.higher_in_tree(idx, highest_child_idx) {
+                return idx;
+            } else {
+                self.swap(idx, highest_child_idx);
+                idx = highest_child_idx;
+            }
+        }
+    }
+
+    /// inserts `val`, returning a stable [`Handle`] that can later be passed to
+    /// [`decrease_key`](Self::decrease_key), [`change_key`](Self::change_key) or [`remove`](Self::remove)
+    pub fn push(&mut self, val: T) -> Handle {
+        let idx = self.vec.len();
+        let handle = Handle(self.position.len());
+        self.position.push(idx);
+        self.removed.push(false);
+        self.vec.push(Entry { handle, val });
+        self.flow_up(idx);
+        handle
+    }
+
+    /// get top element (the minimum for the default comparator), panics on empty
+    pub fn pop(&mut self) -> T {
+        assert!(
+            !self.vec.is_empty(),
+            "Can't pop with no elements in indexed binary heap!"
+        );
+        let root_handle = self.vec[0].handle;
+        self.remove(root_handle)
+    }
+    /// returns reference to top element (the minimum for the default comparator), panics on empty
+    pub fn peek(&self) -> &T {
+        assert!(
+            !self.vec.is_empty(),
+            "Can't peek with no elements in indexed binary heap!"
+        );
+        &self.vec[0].val
+    }
+
+    /// lowers (improves the priority of) the element referenced by `handle` to
+    /// `new_val` and restores the heap invariant in O(log n). Only correct to
+    /// call when `new_val` is higher-priority than the current value; use
+    /// [`change_key`](Self::change_key) if the direction isn't known.
+    ///
+    /// Panics if `handle`'s element was already removed from the heap.
+    pub fn decrease_key(&mut self, handle: Handle, new_val: T) {
+        self.assert_valid(handle);
+        let idx = self.position[handle.0];
+        self.vec[idx].val = new_val;
+        self.flow_up(idx);
+    }
+
+    /// overwrites the value referenced by `handle` with `new_val`, whether that
+    /// raises or lowers its priority, and restores the heap invariant in O(log n).
+    ///
+    /// Panics if `handle`'s element was already removed from the heap.
+    pub fn change_key(&mut self, handle: Handle, new_val: T) {
+        self.assert_valid(handle);
+        let idx = self.position[handle.0];
+        self.vec[idx].val = new_val;
+        let idx = self.flow_up(idx);
+        self.flow_down(idx);
+    }
+
+    /// removes and returns the element referenced by `handle` in O(log n).
+    ///
+    /// Panics if `handle`'s element was already removed from the heap.
+    pub fn remove(&mut self, handle: Handle) -> T {
+        self.assert_valid(handle);
+        let idx = self.position[handle.0];
+        let last = self.vec.len() - 1;
+        self.swap(idx, last);
+        let removed = self.vec.pop().unwrap();
+        self.removed[handle.0] = true;
+        if idx < self.vec.len() {
+            let idx = self.flow_up(idx);
+            self.flow_down(idx);
+        }
+        removed.val
+    }
+}
+
+impl<T: Ord> Default for IndexedBinaryHeap<T, MinComparator> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IndexedBinaryHeap;
+
+    #[test]
+    fn decrease_key_moves_element_to_front() {
+        let mut heap = IndexedBinaryHeap::new();
+        let a = heap.push(10);
+        let b = heap.push(20);
+        let _c = heap.push(30);
+        assert_eq!(*heap.peek(), 10);
+        heap.decrease_key(b, 1);
+        assert_eq!(*heap.peek(), 1);
+        assert_eq!(heap.pop(), 1);
+        assert_eq!(heap.pop(), 10);
+        let _ = a;
+    }
+
+    #[test]
+    fn change_key_handles_both_directions() {
+        let mut heap = IndexedBinaryHeap::new();
+        let a = heap.push(1);
+        let b = heap.push(2);
+        heap.change_key(a, 100); // raise a's value, b should now be the minimum
+        assert_eq!(*heap.peek(), 2);
+        heap.change_key(b, 200);
+        assert_eq!(*heap.peek(), 100);
+        let _ = a;
+    }
+
+    #[test]
+    #[should_panic(expected = "Handle used after its element was removed")]
+    fn reusing_a_removed_handle_panics_instead_of_corrupting_another_element() {
+        let mut heap = IndexedBinaryHeap::new();
+        let _a = heap.push(10);
+        let b = heap.push(20);
+        let _c = heap.push(30);
+        let _d = heap.push(40);
+        heap.remove(b);
+        let refilled = heap.push(99); // may reuse b's old backing-vec slot
+        heap.decrease_key(b, -100); // must panic, not silently rewrite `refilled`
+        let _ = refilled;
+    }
+
+    #[test]
+    fn remove_by_handle_preserves_remaining_order() {
+        let mut heap = IndexedBinaryHeap::new();
+        let handles: Vec<_> = [5, 1, 8, 3, 9].into_iter().map(|v| heap.push(v)).collect();
+        heap.remove(handles[2]); // remove the 8
+        let mut out = Vec::new();
+        while !heap.is_empty() {
+            out.push(heap.pop());
+        }
+        assert_eq!(out, vec![1, 3, 5, 9]);
+    }
+}
@@ -1,13 +1,128 @@
+use std::cmp::Ordering;
+use std::ops::{Deref, DerefMut};
 use std::slice::Iter;
 
-/// Min heap
-pub struct BinaryHeap<T: PartialOrd> {
+pub mod indexed;
+pub use indexed::IndexedBinaryHeap;
+
+mod sift;
+
+pub mod fixed;
+pub use fixed::FixedBinaryHeap;
+
+/// Determines the ordering used by a [`BinaryHeap`]. The element returned by
+/// `pop`/`peek` is the one that compares as [`Ordering::Less`] against every
+/// other element, i.e. `compares(a, b) == Ordering::Less` means `a` comes out
+/// before `b`.
+pub trait Compare<T> {
+    fn compares(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Default comparator: smallest element first (min-heap).
+#[derive(Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+#[repr(C)]
+pub struct MinComparator;
+impl<T: Ord> Compare<T> for MinComparator {
+    fn compares(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// Largest element first (max-heap).
+#[derive(Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+#[repr(C)]
+pub struct MaxComparator;
+impl<T: Ord> Compare<T> for MaxComparator {
+    fn compares(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+/// Wraps a closure `Fn(&T, &T) -> Ordering` as a [`Compare`], used by
+/// [`BinaryHeap::new_by`].
+pub struct FnComparator<F>(F);
+impl<T, F: Fn(&T, &T) -> Ordering> Compare<T> for FnComparator<F> {
+    fn compares(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a, b)
+    }
+}
+
+/// Orders elements by a derived key `Fn(&T) -> K`, used by
+/// [`BinaryHeap::new_by_key`].
+pub struct KeyComparator<F>(F);
+impl<T, K: Ord, F: Fn(&T) -> K> Compare<T> for KeyComparator<F> {
+    fn compares(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a).cmp(&(self.0)(b))
+    }
+}
+
+/// Binary heap, min-heap by default. The comparator `C` decides what "higher
+/// in the tree" (and thus what `pop`/`peek` return) means; see [`Compare`].
+pub struct BinaryHeap<T, C = MinComparator> {
     vec: Vec<T>,
+    cmp: C,
 }
-impl<T: PartialOrd> BinaryHeap<T> {
+impl<T: Ord> BinaryHeap<T, MinComparator> {
     pub fn new() -> Self {
-        BinaryHeap { vec: Vec::new() }
+        BinaryHeap {
+            vec: Vec::new(),
+            cmp: MinComparator,
+        }
     }
+    pub fn from_unsorted_vec(vec: Vec<T>) -> Self {
+        let mut this = Self {
+            vec,
+            cmp: MinComparator,
+        };
+        this.heapify();
+        this
+    }
+    /// creates an empty heap with at least the given capacity preallocated
+    pub fn with_capacity(capacity: usize) -> Self {
+        BinaryHeap {
+            vec: Vec::with_capacity(capacity),
+            cmp: MinComparator,
+        }
+    }
+}
+impl<T: Ord> BinaryHeap<T, MaxComparator> {
+    /// Max-heap: `pop`/`peek` return the largest element.
+    pub fn new_max() -> Self {
+        BinaryHeap {
+            vec: Vec::new(),
+            cmp: MaxComparator,
+        }
+    }
+    /// creates an empty max-heap with at least the given capacity preallocated
+    pub fn with_capacity_max(capacity: usize) -> Self {
+        BinaryHeap {
+            vec: Vec::with_capacity(capacity),
+            cmp: MaxComparator,
+        }
+    }
+}
+impl<T, F: Fn(&T, &T) -> Ordering> BinaryHeap<T, FnComparator<F>> {
+    /// Heap ordered by the given comparison function, smaller meaning
+    /// "comes out first".
+    pub fn new_by(cmp: F) -> Self {
+        BinaryHeap {
+            vec: Vec::new(),
+            cmp: FnComparator(cmp),
+        }
+    }
+}
+impl<T, K: Ord, F: Fn(&T) -> K> BinaryHeap<T, KeyComparator<F>> {
+    /// Heap ordered by `f(element)`, smallest key first.
+    pub fn new_by_key(f: F) -> Self {
+        BinaryHeap {
+            vec: Vec::new(),
+            cmp: KeyComparator(f),
+        }
+    }
+}
+impl<T, C: Compare<T>> BinaryHeap<T, C> {
     pub fn len(&self) -> usize {
         self.vec.len()
     }
@@ -25,51 +140,31 @@ impl<T: PartialOrd> BinaryHeap<T> {
         self.vec.clear();
     }
 
-    fn parent_idx(you: usize) -> usize {
-        debug_assert!(you != 0, "try to get parent of root");
-        (you - 1) >> 1
+    /// total number of elements the backing storage can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+    /// reserves capacity for at least `additional` more elements
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional);
     }
-    fn child_idxs(you: usize) -> (usize, usize) {
-        ((you << 1) + 1, (you << 1) + 2)
+    /// reserves capacity for exactly `additional` more elements
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.vec.reserve_exact(additional);
     }
+    /// shrinks the backing storage to fit the elements currently in the heap
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.shrink_to_fit();
+    }
+
     fn higher_in_tree(&self, you: usize, they: usize) -> bool {
-        self.vec[you] < self.vec[they]
+        self.cmp.compares(&self.vec[you], &self.vec[they]) == Ordering::Less
     }
-    fn flow_up(&mut self, mut idx: usize) {
-        loop {
-            if idx == 0 {
-                return;
-            }
-            let parent_idx = Self::parent_idx(idx);
-            if self.higher_in_tree(idx, parent_idx) {
-                self.vec.swap(parent_idx, idx);
-                idx = parent_idx;
-            } else {
-                return;
-            }
-        }
+    fn flow_up(&mut self, idx: usize) {
+        sift::flow_up(&mut self.vec, &self.cmp, idx)
     }
-    // must always pick min child, as that one can only be parent to the other.
-    /// Can be called on node that doesn't exist, as it will not have any children
-    fn flow_down(&mut self, mut idx: usize) {
-        loop {
-            let (child1_idx, child2_idx) = Self::child_idxs(idx);
-            let highest_child_idx = if child1_idx >= self.vec.len() {
-                return; // don't have any children
-            } else if child2_idx >= self.vec.len() || self.higher_in_tree(child1_idx, child2_idx) {
-                child1_idx // only have left child || OR, left is higher than right
-            } else {
-                child2_idx // child2 was higher, has to be parent
-            };
-            if self.higher_in_tree(idx, highest_child_idx) {
-                // we are higher than both. Stay here
-                return;
-            } else {
-                // we are lower than the highest one, we can ONLY switch places with highest child.
-                self.vec.swap(idx, highest_child_idx);
-                idx = highest_child_idx;
-            }
-        }
+    fn flow_down(&mut self, idx: usize) {
+        sift::flow_down(&mut self.vec, &self.cmp, idx)
     }
     /// recursive version of flow down, may be more performant as it also uses vec_len
     fn flow_down_rec(&mut self, idx: usize, vec_len: usize) {
@@ -82,14 +177,14 @@ impl<T: PartialOrd> BinaryHeap<T> {
             if right_child_idx >= vec_len {
                 break 'blk left_child_idx;
             }
-            if self.vec[left_child_idx] < self.vec[right_child_idx] {
+            if self.higher_in_tree(left_child_idx, right_child_idx) {
                 left_child_idx
             } else {
                 right_child_idx
             }
         };
-        if self.vec[idx] > self.vec[child_idx] {
-            return; // if both are lower, don't do anything
+        if self.higher_in_tree(idx, child_idx) {
+            return; // idx is already higher in the tree than both children
         }
         self.vec.swap(idx, child_idx);
         self.flow_down_rec(child_idx, vec_len);
@@ -121,23 +216,172 @@ impl<T: PartialOrd> BinaryHeap<T> {
         );
         &self.vec[0]
     }
-    pub fn from_unsorted_vec(vec: Vec<T>) -> Self {
-        let mut this = Self {vec};
-        let len = this.len();
+    /// returns a guard giving mutable access to the top element, which re-heapifies
+    /// on drop if the element was actually mutated. `None` if the heap is empty.
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+        if self.vec.is_empty() {
+            None
+        } else {
+            Some(PeekMut {
+                heap: self,
+                sifted: false,
+            })
+        }
+    }
+    /// restores the heap invariant over the whole backing vec, bottom-up
+    fn heapify(&mut self) {
+        let len = self.len();
+        if len < 2 {
+            return;
+        }
         let last_node_with_child = (len - 2) >> 1; // get node that FOR SURE has at least one child
         for idx in (0..=last_node_with_child).rev() {
-            this.flow_down(idx);
+            self.flow_down(idx);
         }
+    }
+
+    /// consumes the heap and returns the elements in the order repeated `pop`s
+    /// would yield (ascending for the default min-heap), sorting in place with
+    /// no extra allocation
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut end = self.vec.len();
+        while end > 1 {
+            end -= 1;
+            self.vec.swap(0, end);
+            self.flow_down_rec(0, end);
+        }
+        self.vec.reverse();
+        self.vec
+    }
+
+    /// consumes the heap and hands back the backing storage in arbitrary heap order
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+
+    /// consumes the heap, yielding elements in the order repeated `pop`s would
+    /// yield (ascending for the default min-heap), one `pop` per `next` call
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, C> {
+        IntoIterSorted { heap: self }
+    }
+
+    /// drains the heap, yielding elements in pop order (ascending for the default
+    /// min-heap), one `pop` per `next` call
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T, C> {
+        DrainSorted { heap: self }
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for BinaryHeap<T, C> {
+    /// builds the heap in O(n) by collecting into a `Vec` once and heapifying,
+    /// rather than pushing one element at a time
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut this = BinaryHeap {
+            vec: iter.into_iter().collect(),
+            cmp: C::default(),
+        };
+        this.heapify();
         this
     }
 }
 
-impl<T: PartialOrd> Default for BinaryHeap<T> {
+impl<T, C: Compare<T>> Extend<T> for BinaryHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for val in iter {
+            self.push(val);
+        }
+    }
+}
+
+/// owned iterator over a [`BinaryHeap`]'s elements in arbitrary heap order,
+/// returned by `IntoIterator::into_iter`
+impl<T, C> IntoIterator for BinaryHeap<T, C> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+/// yields elements of a [`BinaryHeap`] in pop order, returned by
+/// [`BinaryHeap::into_iter_sorted`]
+pub struct IntoIterSorted<T, C> {
+    heap: BinaryHeap<T, C>,
+}
+impl<T, C: Compare<T>> Iterator for IntoIterSorted<T, C> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        if self.heap.is_empty() {
+            None
+        } else {
+            Some(self.heap.pop())
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.heap.len();
+        (len, Some(len))
+    }
+}
+
+/// drains a [`BinaryHeap`] in pop order, returned by [`BinaryHeap::drain_sorted`]
+pub struct DrainSorted<'a, T, C: Compare<T>> {
+    heap: &'a mut BinaryHeap<T, C>,
+}
+impl<T, C: Compare<T>> Iterator for DrainSorted<'_, T, C> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        if self.heap.is_empty() {
+            None
+        } else {
+            Some(self.heap.pop())
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.heap.len();
+        (len, Some(len))
+    }
+}
+impl<T, C: Compare<T>> Drop for DrainSorted<'_, T, C> {
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
+
+impl<T: Ord> Default for BinaryHeap<T, MinComparator> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Guard returned by [`BinaryHeap::peek_mut`]. Derefs to `&T`; deref-mutating it
+/// marks the heap as needing to re-sift, which happens automatically on drop.
+pub struct PeekMut<'a, T, C: Compare<T>> {
+    heap: &'a mut BinaryHeap<T, C>,
+    sifted: bool,
+}
+
+impl<T, C: Compare<T>> Drop for PeekMut<'_, T, C> {
+    fn drop(&mut self) {
+        if self.sifted {
+            self.heap.flow_down(0);
+        }
+    }
+}
+
+impl<T, C: Compare<T>> Deref for PeekMut<'_, T, C> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.heap.vec[0]
+    }
+}
+
+impl<T, C: Compare<T>> DerefMut for PeekMut<'_, T, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.sifted = true;
+        &mut self.heap.vec[0]
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{fs::File, io::Read};
@@ -160,7 +404,7 @@ mod test {
             let num = u64::from_le_bytes(num);
             match buf[0] % 3 {
                 0 => {
-                    
+
                     a.push(num);
                     b.push(std::cmp::Reverse(num));
                 }
@@ -176,7 +420,7 @@ mod test {
                 }
                 _ => panic!()
             }
-            
+
         }
         println!("watermark len: {}", watermark_len);
     }
@@ -198,4 +442,143 @@ mod test {
         assert_eq!(dump, check);
         //println!("{:?}", check);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn into_sorted_vec_is_ascending() {
+        use crate::BinaryHeap as MyHeap;
+        let mut heap = MyHeap::new();
+        for v in [5, 1, 8, 3, 9, 2, 7] {
+            heap.push(v);
+        }
+        assert_eq!(heap.into_sorted_vec(), vec![1, 2, 3, 5, 7, 8, 9]);
+    }
+
+    #[test]
+    fn into_vec_contains_all_pushed_elements() {
+        use crate::BinaryHeap as MyHeap;
+        let mut heap = MyHeap::new();
+        for v in [5, 1, 8, 3] {
+            heap.push(v);
+        }
+        let mut vec = heap.into_vec();
+        vec.sort();
+        assert_eq!(vec, vec![1, 3, 5, 8]);
+    }
+
+    #[test]
+    fn peek_mut_reheapifies_on_drop() {
+        use crate::BinaryHeap as MyHeap;
+        let mut heap = MyHeap::new();
+        for v in [5, 1, 8, 3, 9] {
+            heap.push(v);
+        }
+        assert_eq!(*heap.peek_mut().unwrap(), 1);
+        *heap.peek_mut().unwrap() += 100; // 1 -> 101, no longer the minimum
+        assert_eq!(heap.into_sorted_vec(), vec![3, 5, 8, 9, 101]);
+    }
+
+    #[test]
+    fn peek_mut_without_mutation_does_not_resift() {
+        use crate::BinaryHeap as MyHeap;
+        let mut heap = MyHeap::new();
+        for v in [5, 1, 8] {
+            heap.push(v);
+        }
+        {
+            let guard = heap.peek_mut().unwrap();
+            assert_eq!(*guard, 1);
+        }
+        assert_eq!(*heap.peek(), 1);
+    }
+
+    #[test]
+    fn with_capacity_preallocates_without_affecting_order() {
+        use crate::BinaryHeap;
+        let mut heap: BinaryHeap<i32> = BinaryHeap::with_capacity(10);
+        assert!(heap.capacity() >= 10);
+        heap.extend([5, 1, 8, 3]);
+        assert_eq!(heap.into_sorted_vec(), vec![1, 3, 5, 8]);
+    }
+
+    #[test]
+    fn reserve_and_shrink_to_fit_do_not_lose_elements() {
+        use crate::BinaryHeap;
+        let mut heap = BinaryHeap::new();
+        heap.extend([5, 1, 8]);
+        heap.reserve(100);
+        assert!(heap.capacity() >= 103);
+        heap.shrink_to_fit();
+        assert_eq!(heap.into_sorted_vec(), vec![1, 5, 8]);
+    }
+
+    #[test]
+    fn max_heap_pops_largest_first() {
+        use crate::BinaryHeap;
+        let mut heap: BinaryHeap<i32, crate::MaxComparator> = BinaryHeap::new_max();
+        for v in [5, 1, 8, 3, 9, 2] {
+            heap.push(v);
+        }
+        let mut out = Vec::new();
+        while !heap.is_empty() {
+            out.push(heap.pop());
+        }
+        assert_eq!(out, vec![9, 8, 5, 3, 2, 1]);
+    }
+
+    #[test]
+    fn key_heap_orders_by_derived_key() {
+        use crate::BinaryHeap;
+        let mut heap = BinaryHeap::new_by_key(|x: &(i32, &str)| x.0);
+        heap.push((3, "c"));
+        heap.push((1, "a"));
+        heap.push((2, "b"));
+        assert_eq!(heap.pop(), (1, "a"));
+        assert_eq!(heap.pop(), (2, "b"));
+        assert_eq!(heap.pop(), (3, "c"));
+    }
+
+    #[test]
+    fn collects_from_iterator_in_ascending_pop_order() {
+        use crate::BinaryHeap;
+        let heap: BinaryHeap<i32> = [5, 1, 8, 3, 9].into_iter().collect();
+        assert_eq!(heap.into_sorted_vec(), vec![1, 3, 5, 8, 9]);
+    }
+
+    #[test]
+    fn extend_pushes_every_item() {
+        use crate::BinaryHeap;
+        let mut heap = BinaryHeap::new();
+        heap.push(5);
+        heap.extend([1, 8, 3]);
+        assert_eq!(heap.into_sorted_vec(), vec![1, 3, 5, 8]);
+    }
+
+    #[test]
+    fn owned_into_iter_yields_all_elements_in_arbitrary_order() {
+        use crate::BinaryHeap;
+        let mut heap = BinaryHeap::new();
+        heap.extend([5, 1, 8, 3]);
+        let mut collected: Vec<i32> = heap.into_iter().collect();
+        collected.sort();
+        assert_eq!(collected, vec![1, 3, 5, 8]);
+    }
+
+    #[test]
+    fn into_iter_sorted_is_ascending() {
+        use crate::BinaryHeap;
+        let mut heap = BinaryHeap::new();
+        heap.extend([5, 1, 8, 3]);
+        let collected: Vec<i32> = heap.into_iter_sorted().collect();
+        assert_eq!(collected, vec![1, 3, 5, 8]);
+    }
+
+    #[test]
+    fn drain_sorted_empties_the_heap_in_ascending_order() {
+        use crate::BinaryHeap;
+        let mut heap = BinaryHeap::new();
+        heap.extend([5, 1, 8, 3]);
+        let collected: Vec<i32> = heap.drain_sorted().collect();
+        assert_eq!(collected, vec![1, 3, 5, 8]);
+        assert!(heap.is_empty());
+    }
+}
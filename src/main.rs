@@ -22,6 +22,7 @@ fn main() {
 struct BinaryHeap<T: PartialOrd> {
     vec: Vec<T>,
 }
+#[allow(dead_code)]
 impl<T: PartialOrd> BinaryHeap<T> {
     pub fn new() -> Self {
         BinaryHeap { vec: Vec::new() }
@@ -141,6 +142,7 @@ impl<T: PartialOrd> BinaryHeap<T> {
 }
 
 
+#[cfg(test)]
 mod test {
     use std::{fs::File, io::Read};
 
@@ -0,0 +1,52 @@
+//! Sift-up/down primitives operating on a plain `&mut [T]`, shared between the
+//! `Vec`-backed [`crate::BinaryHeap`] and the array-backed [`crate::fixed::FixedBinaryHeap`].
+
+use crate::Compare;
+use std::cmp::Ordering;
+
+pub(crate) fn parent_idx(you: usize) -> usize {
+    debug_assert!(you != 0, "try to get parent of root");
+    (you - 1) >> 1
+}
+pub(crate) fn child_idxs(you: usize) -> (usize, usize) {
+    ((you << 1) + 1, (you << 1) + 2)
+}
+fn higher_in_tree<T, C: Compare<T>>(data: &[T], cmp: &C, you: usize, they: usize) -> bool {
+    cmp.compares(&data[you], &data[they]) == Ordering::Less
+}
+pub(crate) fn flow_up<T, C: Compare<T>>(data: &mut [T], cmp: &C, mut idx: usize) {
+    loop {
+        if idx == 0 {
+            return;
+        }
+        let parent_idx = parent_idx(idx);
+        if higher_in_tree(data, cmp, idx, parent_idx) {
+            data.swap(parent_idx, idx);
+            idx = parent_idx;
+        } else {
+            return;
+        }
+    }
+}
+// must always pick min child, as that one can only be parent to the other.
+/// Can be called on node that doesn't exist, as it will not have any children
+pub(crate) fn flow_down<T, C: Compare<T>>(data: &mut [T], cmp: &C, mut idx: usize) {
+    loop {
+        let (child1_idx, child2_idx) = child_idxs(idx);
+        let highest_child_idx = if child1_idx >= data.len() {
+            return; // don't have any children
+        } else if child2_idx >= data.len() || higher_in_tree(data, cmp, child1_idx, child2_idx) {
+            child1_idx // only have left child || OR, left is higher than right
+        } else {
+            child2_idx // child2 was higher, has to be parent
+        };
+        if higher_in_tree(data, cmp, idx, highest_child_idx) {
+            // we are higher than both. Stay here
+            return;
+        } else {
+            // we are lower than the highest one, we can ONLY switch places with highest child.
+            data.swap(idx, highest_child_idx);
+            idx = highest_child_idx;
+        }
+    }
+}